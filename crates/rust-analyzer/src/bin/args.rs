@@ -7,7 +7,7 @@ use std::{env, fmt::Write, path::PathBuf};
 
 use anyhow::{bail, Result};
 use pico_args::Arguments;
-use rust_analyzer::cli::{AnalysisStatsCmd, BenchCmd, BenchWhat, Position, Verbosity};
+use rust_analyzer::cli::{AnalysisStatsCmd, BenchCmd, BenchWhat, OutputFormat, Position, Verbosity};
 use ssr::{SsrPattern, SsrRule};
 use vfs::AbsPathBuf;
 
@@ -18,12 +18,13 @@ pub(crate) struct Args {
 }
 
 pub(crate) enum Command {
-    Parse { no_dump: bool },
-    Symbols,
-    Highlight { rainbow: bool },
+    Parse { no_dump: bool, path: Option<PathBuf> },
+    Symbols { path: Option<PathBuf> },
+    Highlight { rainbow: bool, path: Option<PathBuf> },
     AnalysisStats(AnalysisStatsCmd),
     Bench(BenchCmd),
-    Diagnostics { path: PathBuf, load_output_dirs: bool, with_proc_macro: bool },
+    Diagnostics { path: PathBuf, load_output_dirs: bool, with_proc_macro: bool, format: OutputFormat },
+    Lsif { path: PathBuf, load_output_dirs: bool, with_proc_macro: bool },
     Ssr { rules: Vec<SsrRule> },
     StructuredSearch { debug_snippet: Option<String>, patterns: Vec<SsrPattern> },
     ProcMacro,
@@ -56,12 +57,15 @@ COMMANDS:
 
 not specified         Launch LSP server
 
-parse < main.rs       Parse tree
+parse [PATH]          Parse tree
+    <PATH>            Read from this file instead of stdin
     --no-dump         Suppress printing
 
-symbols < main.rs     Parse input an print the list of symbols
+symbols [PATH]        Parse input an print the list of symbols
+    <PATH>            Read from this file instead of stdin
 
-highlight < main.rs   Highlight input as html
+highlight [PATH]      Highlight input as html
+    <PATH>            Read from this file instead of stdin
     --rainbow         Enable rainbow highlighting of identifiers
 
 analysis-stats <PATH> Batch typecheck project and print summary statistics
@@ -74,6 +78,7 @@ analysis-stats <PATH> Batch typecheck project and print summary statistics
     --load-output-dirs
                       Load OUT_DIR values by running `cargo check` before analysis
     --with-proc-macro Use proc-macro-srv for proc-macro expanding
+    --format <FMT>    Output format, `human` (default) or `json`
 
 analysis-bench <PATH> Benchmark specific analysis operation
     <PATH>            Directory with Cargo.toml
@@ -93,6 +98,13 @@ diagnostics <PATH>
     --load-output-dirs
                       Load OUT_DIR values by running `cargo check` before analysis
     --with-proc-macro Use proc-macro-srv for proc-macro expanding
+    --format <FMT>    Output format, `human` (default) or `json`
+
+lsif <PATH>           Dump project as an LSIF graph (newline-delimited JSON)
+    <PATH>            Directory with Cargo.toml
+    --load-output-dirs
+                      Load OUT_DIR values by running `cargo check` before analysis
+    --with-proc-macro Use proc-macro-srv for proc-macro expanding
 
 ssr [RULE...]
     <RULE>            A structured search replace rule (`$a.foo($b) ==> bar($a, $b)`)
@@ -147,17 +159,20 @@ impl Args {
         let command = match subcommand.as_str() {
             "parse" => {
                 let no_dump = matches.contains("--no-dump");
+                let path = opt_path(&mut matches)?;
                 matches.finish().or_else(handle_extra_flags)?;
-                Command::Parse { no_dump }
+                Command::Parse { no_dump, path }
             }
             "symbols" => {
+                let path = opt_path(&mut matches)?;
                 matches.finish().or_else(handle_extra_flags)?;
-                Command::Symbols
+                Command::Symbols { path }
             }
             "highlight" => {
                 let rainbow = matches.contains("--rainbow");
+                let path = opt_path(&mut matches)?;
                 matches.finish().or_else(handle_extra_flags)?;
-                Command::Highlight { rainbow }
+                Command::Highlight { rainbow, path }
             }
             "analysis-stats" => {
                 let randomize = matches.contains("--randomize");
@@ -167,13 +182,8 @@ impl Args {
                 let with_deps: bool = matches.contains("--with-deps");
                 let load_output_dirs = matches.contains("--load-output-dirs");
                 let with_proc_macro = matches.contains("--with-proc-macro");
-                let path = {
-                    let mut trailing = matches.free()?;
-                    if trailing.len() != 1 {
-                        bail!("Invalid flags");
-                    }
-                    trailing.pop().unwrap().into()
-                };
+                let format = parse_output_format(&mut matches)?;
+                let path = req_path(&mut matches)?;
 
                 Command::AnalysisStats(AnalysisStatsCmd {
                     randomize,
@@ -184,6 +194,7 @@ impl Args {
                     path,
                     load_output_dirs,
                     with_proc_macro,
+                    format,
                 })
             }
             "analysis-bench" => {
@@ -204,14 +215,7 @@ impl Args {
                 let memory_usage = matches.contains("--memory-usage");
                 let load_output_dirs = matches.contains("--load-output-dirs");
                 let with_proc_macro = matches.contains("--with-proc-macro");
-
-                let path = {
-                    let mut trailing = matches.free()?;
-                    if trailing.len() != 1 {
-                        bail!("Invalid flags");
-                    }
-                    trailing.pop().unwrap().into()
-                };
+                let path = req_path(&mut matches)?;
 
                 Command::Bench(BenchCmd {
                     memory_usage,
@@ -224,15 +228,17 @@ impl Args {
             "diagnostics" => {
                 let load_output_dirs = matches.contains("--load-output-dirs");
                 let with_proc_macro = matches.contains("--with-proc-macro");
-                let path = {
-                    let mut trailing = matches.free()?;
-                    if trailing.len() != 1 {
-                        bail!("Invalid flags");
-                    }
-                    trailing.pop().unwrap().into()
-                };
+                let format = parse_output_format(&mut matches)?;
+                let path = req_path(&mut matches)?;
 
-                Command::Diagnostics { path, load_output_dirs, with_proc_macro }
+                Command::Diagnostics { path, load_output_dirs, with_proc_macro, format }
+            }
+            "lsif" => {
+                let load_output_dirs = matches.contains("--load-output-dirs");
+                let with_proc_macro = matches.contains("--with-proc-macro");
+                let path = req_path(&mut matches)?;
+
+                Command::Lsif { path, load_output_dirs, with_proc_macro }
             }
             "proc-macro" => Command::ProcMacro,
             "ssr" => {
@@ -259,6 +265,34 @@ impl Args {
     }
 }
 
+fn opt_path(matches: &mut Arguments) -> Result<Option<PathBuf>> {
+    let trailing = matches.free()?;
+    match trailing.len() {
+        0 => Ok(None),
+        1 => Ok(Some(trailing.into_iter().next().unwrap().into())),
+        _ => bail!("Invalid flags"),
+    }
+}
+
+fn req_path(matches: &mut Arguments) -> Result<PathBuf> {
+    let mut trailing = matches.free()?;
+    if trailing.len() != 1 {
+        bail!("Invalid flags");
+    }
+    Ok(trailing.pop().unwrap().into())
+}
+
+fn parse_output_format(matches: &mut Arguments) -> Result<OutputFormat> {
+    match matches.opt_value_from_str::<_, String>("--format")? {
+        Some(v) => match v.as_str() {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            _ => bail!("Invalid --format value: `{}` (expected `human` or `json`)", v),
+        },
+        None => Ok(OutputFormat::Human),
+    }
+}
+
 fn handle_extra_flags(e: pico_args::Error) -> Result<()> {
     if let pico_args::Error::UnusedArgsLeft(flags) = e {
         let mut invalid_flags = String::new();
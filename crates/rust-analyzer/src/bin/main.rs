@@ -0,0 +1,36 @@
+//! Entry point: parses the command line and either starts the LSP server or
+//! runs one of the batch subcommands.
+
+mod args;
+
+use anyhow::Result;
+use rust_analyzer::cli;
+
+use crate::args::{Args, Command};
+
+fn main() -> Result<()> {
+    let args = Args::parse()?;
+
+    match args.command {
+        Command::RunServer => rust_analyzer::run_server()?,
+        Command::ProcMacro => cli::run_proc_macro_srv()?,
+        Command::Parse { no_dump, path } => cli::parse(no_dump, path)?,
+        Command::Symbols { path } => cli::symbols(path)?,
+        Command::Highlight { rainbow, path } => cli::highlight(rainbow, path)?,
+        Command::AnalysisStats(cmd) => cmd.run()?,
+        Command::Bench(cmd) => cmd.run()?,
+        Command::Diagnostics { path, load_output_dirs, with_proc_macro, format } => {
+            cli::diagnostics(path, load_output_dirs, with_proc_macro, format)?
+        }
+        Command::Lsif { path, load_output_dirs, with_proc_macro } => {
+            cli::lsif(path, load_output_dirs, with_proc_macro)?
+        }
+        Command::Ssr { rules } => cli::apply_ssr_rules(rules)?,
+        Command::StructuredSearch { patterns, debug_snippet } => {
+            cli::search_for_patterns(patterns, debug_snippet)?
+        }
+        Command::Version => println!("rust-analyzer {}", rust_analyzer::version()),
+        Command::Help => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! The `analysis-stats` batch command: type-check a whole workspace and
+//! print summary counters, either as human-readable text or as JSON.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use rayon::prelude::*;
+use vfs::FileId;
+
+use crate::cli::{load_workspace, OutputFormat};
+
+pub struct AnalysisStatsCmd {
+    pub randomize: bool,
+    pub parallel: bool,
+    pub memory_usage: bool,
+    pub only: Option<String>,
+    pub with_deps: bool,
+    pub path: PathBuf,
+    pub load_output_dirs: bool,
+    pub with_proc_macro: bool,
+    pub format: OutputFormat,
+}
+
+#[derive(Default)]
+struct Stats {
+    items_processed: u64,
+    type_mismatches: u64,
+    total_time_ms: u128,
+    memory_usage_bytes: Option<u64>,
+}
+
+impl AnalysisStatsCmd {
+    pub fn run(self) -> Result<()> {
+        let start = Instant::now();
+        let (host, vfs) = load_workspace(&self.path, self.load_output_dirs, self.with_proc_macro)?;
+
+        let root = self.path.canonicalize().unwrap_or_else(|_| self.path.clone());
+        let mut files: Vec<FileId> = vfs
+            .iter()
+            .filter(|(_, file_path)| {
+                self.with_deps || Path::new(&file_path.to_string()).starts_with(&root)
+            })
+            .filter(|(_, file_path)| {
+                self.only.as_deref().map_or(true, |only| file_path.to_string().contains(only))
+            })
+            .map(|(file_id, _)| file_id)
+            .collect();
+        if self.randomize {
+            files.shuffle(&mut rand::thread_rng());
+        }
+
+        // `AnalysisHost::analysis` takes `&self` and hands back a fresh,
+        // independent salsa snapshot each call, so every rayon worker gets
+        // its own `Analysis` instead of sharing one across threads (a
+        // `Snapshot` is `Send` but not `Sync`, so a shared `&Analysis`
+        // wouldn't satisfy rayon's bounds, and would serialize/alias state
+        // even if it did).
+        let per_file_stats: Vec<(u64, u64)> = if self.parallel {
+            files.par_iter().map(|&file_id| per_file_stats(&host.analysis(), file_id)).collect()
+        } else {
+            let analysis = host.analysis();
+            files.iter().map(|&file_id| per_file_stats(&analysis, file_id)).collect()
+        };
+
+        let mut stats = Stats::default();
+        for (items, type_mismatches) in per_file_stats {
+            stats.items_processed += items;
+            stats.type_mismatches += type_mismatches;
+        }
+
+        stats.total_time_ms = start.elapsed().as_millis();
+        if self.memory_usage {
+            stats.memory_usage_bytes = Some(host.raw_database_memory_usage());
+        }
+
+        match self.format {
+            OutputFormat::Human => print_human(&stats),
+            OutputFormat::Json => print_json(&stats)?,
+        }
+        Ok(())
+    }
+}
+
+/// `(items processed, type mismatches)` for one file.
+fn per_file_stats(analysis: &ide::Analysis, file_id: FileId) -> (u64, u64) {
+    let items = analysis.file_structure(file_id).map_or(0, |structure| structure.len() as u64);
+    let type_mismatches = analysis
+        .diagnostics(&Default::default(), ide::AssistResolveStrategy::None, file_id)
+        .map_or(0, |diagnostics| {
+            diagnostics.iter().filter(|d| d.code.as_str() == "type-mismatch").count() as u64
+        });
+    (items, type_mismatches)
+}
+
+fn print_human(stats: &Stats) {
+    println!("Item count:      {}", stats.items_processed);
+    println!("Type mismatches: {}", stats.type_mismatches);
+    println!("Total time:      {}ms", stats.total_time_ms);
+    if let Some(bytes) = stats.memory_usage_bytes {
+        println!("Memory usage:    {} bytes", bytes);
+    }
+}
+
+fn print_json(stats: &Stats) -> Result<()> {
+    let value = serde_json::json!({
+        "items_processed": stats.items_processed,
+        "type_mismatches": stats.type_mismatches,
+        "total_time_ms": stats.total_time_ms,
+        "memory_usage_bytes": stats.memory_usage_bytes,
+    });
+    println!("{}", serde_json::to_string(&value)?);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! The `diagnostics` batch command: run full project analysis and print
+//! every diagnostic, either as human-readable text or as JSON.
+//!
+//! `line_col` below is a UTF-8 (byte) column, not a UTF-16 one, so the JSON
+//! range's `character` is labelled `character_utf8` — same byte-based
+//! convention as the `lsif` command's `range` vertices.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::cli::{load_workspace, OutputFormat};
+
+pub fn diagnostics(
+    path: PathBuf,
+    load_output_dirs: bool,
+    with_proc_macro: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let (host, vfs) = load_workspace(&path, load_output_dirs, with_proc_macro)?;
+    let analysis = host.analysis();
+
+    for (file_id, file_path) in vfs.iter() {
+        let line_index = analysis.file_line_index(file_id)?;
+        for d in analysis.diagnostics(&Default::default(), ide::AssistResolveStrategy::None, file_id)? {
+            let start = line_index.line_col(d.range.start());
+            let end = line_index.line_col(d.range.end());
+            match format {
+                OutputFormat::Human => println!(
+                    "{}:{}:{}: {:?}: {}",
+                    file_path,
+                    start.line + 1,
+                    start.col + 1,
+                    d.severity,
+                    d.message
+                ),
+                OutputFormat::Json => {
+                    let value = serde_json::json!({
+                        "file": file_path.to_string(),
+                        "range": {
+                            "start": { "line": start.line, "character_utf8": start.col },
+                            "end": { "line": end.line, "character_utf8": end.col },
+                            "start_byte": u32::from(d.range.start()),
+                            "end_byte": u32::from(d.range.end()),
+                        },
+                        "severity": format!("{:?}", d.severity),
+                        "code": d.code.as_str(),
+                        "message": d.message,
+                    });
+                    println!("{}", serde_json::to_string(&value)?);
+                }
+            }
+        }
+    }
+    Ok(())
+}
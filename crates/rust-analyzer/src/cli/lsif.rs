@@ -0,0 +1,235 @@
+//! The `lsif` batch command: walk a whole project and stream it out as a
+//! Language Server Index Format (LSIF) graph — newline-delimited JSON
+//! `vertex`/`edge` records describing hover, definition, references, and
+//! document ranges, so the dump can be ingested by code-navigation indexers.
+//!
+//! Invariants the writer below has to uphold: every id is assigned once, in
+//! [`Lsif::emit`], and only ever referenced by records emitted afterwards;
+//! every `range` is `contains`-ed by exactly one `document`; ranges never
+//! overlap within a document (guaranteed here because we only ever emit one
+//! range per source token, and tokens don't overlap); and a `resultSet` is
+//! shared by every token that resolves to the same definition, so repeated
+//! uses of a symbol collapse onto one definition/hover/references triple
+//! instead of duplicating them per occurrence.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use ide::{FilePosition, FileRange, TextRange};
+use serde_json::{json, Value};
+use syntax::{AstNode, NodeOrToken, SourceFile, SyntaxKind};
+use vfs::{FileId, Vfs};
+
+use crate::cli::load_workspace;
+
+type Id = u64;
+
+/// Writes one JSON object per `vertex`/`edge` to `out` as soon as it's
+/// produced — the graph itself is never buffered, only the small lookup
+/// tables (below) needed to dedupe resultSets and to group references by
+/// document are kept in memory.
+struct Lsif<W: Write> {
+    out: W,
+    next_id: Id,
+}
+
+impl<W: Write> Lsif<W> {
+    fn new(out: W) -> Self {
+        Lsif { out, next_id: 0 }
+    }
+
+    fn emit(&mut self, mut value: Value) -> Result<Id> {
+        let id = self.next_id;
+        self.next_id += 1;
+        value["id"] = json!(id);
+        serde_json::to_writer(&mut self.out, &value)?;
+        self.out.write_all(b"\n")?;
+        Ok(id)
+    }
+}
+
+/// Everything gathered about one definition while walking its document, kept
+/// around so the references that show up in *other* documents can still be
+/// attached to it afterwards.
+struct ResultSet {
+    id: Id,
+    /// Ranges (by document) that are definitions of this symbol.
+    definitions: HashMap<Id, Vec<Id>>,
+    /// Ranges (by document) that reference this symbol.
+    references: HashMap<Id, Vec<Id>>,
+    hover: Option<String>,
+}
+
+pub fn lsif(path: PathBuf, load_output_dirs: bool, with_proc_macro: bool) -> Result<()> {
+    let (host, vfs) = load_workspace(&path, load_output_dirs, with_proc_macro)?;
+    let analysis = host.analysis();
+    let stdout = io::stdout();
+    let mut w = Lsif::new(stdout.lock());
+
+    // `LineIndex::line_col` below returns UTF-8 (byte) columns, not UTF-16
+    // ones, so advertise that rather than silently emitting wrong columns
+    // for any line with a multibyte character before a token.
+    let meta_id = w.emit(json!({
+        "type": "vertex",
+        "label": "metaData",
+        "version": "0.4.3",
+        "positionEncoding": "utf-8",
+        "toolInfo": { "name": "rust-analyzer" },
+    }))?;
+    let project_id = w.emit(json!({ "type": "vertex", "label": "project", "kind": "rust" }))?;
+    w.emit(json!({ "type": "edge", "label": "belongsTo", "outV": meta_id, "inV": project_id }))?;
+
+    // Key: the definition's (file, range) — the one stable identity a
+    // resultSet can be looked up by regardless of which document the
+    // current token lives in.
+    let mut result_sets: HashMap<(FileId, TextRange), ResultSet> = HashMap::new();
+    let mut document_ids = Vec::new();
+
+    for (file_id, file_path) in vfs.iter() {
+        let document_id = w.emit(json!({
+            "type": "vertex",
+            "label": "document",
+            "uri": format!("file://{}", file_path),
+            "languageId": "rust",
+        }))?;
+        document_ids.push(document_id);
+
+        let text = analysis.file_text(file_id)?;
+        let line_index = analysis.file_line_index(file_id)?;
+        let tree = SourceFile::parse(&text).tree();
+
+        let mut range_ids = Vec::new();
+        for token in tree.syntax().descendants_with_tokens().filter_map(NodeOrToken::into_token) {
+            if token.kind() != SyntaxKind::IDENT {
+                continue;
+            }
+            let range = token.text_range();
+            let start = line_index.line_col(range.start());
+            let end = line_index.line_col(range.end());
+            let range_id = w.emit(json!({
+                "type": "vertex",
+                "label": "range",
+                "start": { "line": start.line, "character": start.col },
+                "end": { "line": end.line, "character": end.col },
+            }))?;
+            range_ids.push(range_id);
+
+            let pos = FilePosition { file_id, offset: range.start() };
+            let Some(def) = first_definition(&analysis, pos)? else { continue };
+
+            let is_definition = def == (file_id, range);
+            let result_set_id = match result_sets.get(&def) {
+                Some(rs) => rs.id,
+                None => {
+                    let id = w.emit(json!({ "type": "vertex", "label": "resultSet" }))?;
+                    let hover = analysis
+                        .hover(&Default::default(), FileRange { file_id: def.0, range: def.1 })?
+                        .map(|info| info.info.markup.to_string());
+                    result_sets.insert(
+                        def,
+                        ResultSet {
+                            id,
+                            definitions: HashMap::new(),
+                            references: HashMap::new(),
+                            hover,
+                        },
+                    );
+                    id
+                }
+            };
+            w.emit(json!({ "type": "edge", "label": "next", "outV": range_id, "inV": result_set_id }))?;
+
+            let rs = result_sets.get_mut(&def).unwrap();
+            let bucket = if is_definition { &mut rs.definitions } else { &mut rs.references };
+            bucket.entry(document_id).or_default().push(range_id);
+        }
+
+        w.emit(json!({ "type": "edge", "label": "contains", "outV": document_id, "inVs": range_ids }))?;
+    }
+
+    w.emit(json!({ "type": "edge", "label": "contains", "outV": project_id, "inVs": document_ids }))?;
+
+    // Definition/hover/reference data hangs off each resultSet now that
+    // every document (and therefore every id it might point at) has been
+    // emitted.
+    for rs in result_sets.into_values() {
+        if let Some(hover) = rs.hover {
+            let hover_result_id = w.emit(json!({
+                "type": "vertex",
+                "label": "hoverResult",
+                "result": { "contents": hover },
+            }))?;
+            w.emit(json!({
+                "type": "edge",
+                "label": "textDocument/hover",
+                "outV": rs.id,
+                "inV": hover_result_id,
+            }))?;
+        }
+
+        if !rs.definitions.is_empty() {
+            let definition_result_id = w.emit(json!({ "type": "vertex", "label": "definitionResult" }))?;
+            w.emit(json!({
+                "type": "edge",
+                "label": "textDocument/definition",
+                "outV": rs.id,
+                "inV": definition_result_id,
+            }))?;
+            for (document_id, ranges) in &rs.definitions {
+                w.emit(json!({
+                    "type": "edge",
+                    "label": "item",
+                    "outV": definition_result_id,
+                    "inVs": ranges,
+                    "document": document_id,
+                }))?;
+            }
+        }
+
+        if !rs.definitions.is_empty() || !rs.references.is_empty() {
+            let reference_result_id = w.emit(json!({ "type": "vertex", "label": "referenceResult" }))?;
+            w.emit(json!({
+                "type": "edge",
+                "label": "textDocument/references",
+                "outV": rs.id,
+                "inV": reference_result_id,
+            }))?;
+            for (document_id, ranges) in &rs.definitions {
+                w.emit(json!({
+                    "type": "edge",
+                    "label": "item",
+                    "outV": reference_result_id,
+                    "inVs": ranges,
+                    "document": document_id,
+                    "property": "definitions",
+                }))?;
+            }
+            for (document_id, ranges) in &rs.references {
+                w.emit(json!({
+                    "type": "edge",
+                    "label": "item",
+                    "outV": reference_result_id,
+                    "inVs": ranges,
+                    "document": document_id,
+                    "property": "references",
+                }))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The `(file, range)` of the token's definition, or `None` for tokens that
+/// don't resolve to one (keywords, unresolved names, ...).
+fn first_definition(
+    analysis: &ide::Analysis,
+    pos: FilePosition,
+) -> Result<Option<(FileId, TextRange)>> {
+    let Some(nav) = analysis.goto_definition(pos)? else { return Ok(None) };
+    Ok(nav.info.into_iter().next().map(|target| (target.file_id, target.focus_or_full_range())))
+}
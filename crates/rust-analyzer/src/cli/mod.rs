@@ -0,0 +1,159 @@
+//! Batch-mode entry points invoked as `rust-analyzer <subcommand>`.
+//!
+//! `src/bin/main.rs` parses the command line into a `Command` and dispatches
+//! into this module; each subcommand gets its own function (or, once it grows
+//! enough options, its own submodule and a `*Cmd` struct).
+
+mod analysis_stats;
+mod diagnostics;
+mod lsif;
+
+pub use analysis_stats::AnalysisStatsCmd;
+pub use diagnostics::diagnostics;
+pub use lsif::lsif;
+
+use std::{
+    env,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Result;
+use ide::{Analysis, AnalysisHost};
+use load_cargo::{load_workspace_at, LoadCargoConfig};
+use project_model::CargoConfig;
+use vfs::{AbsPathBuf, Vfs};
+
+/// Machine- vs. human-readable output for the batch commands (`diagnostics`,
+/// `analysis-stats`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+/// How chatty the server/CLI should be.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Spammy,
+    Verbose,
+    Normal,
+    Quiet,
+}
+
+/// A `PATH:LINE:COLUMN` location, as accepted by `analysis-bench`'s
+/// `--complete`/`--goto-def`.
+pub struct Position {
+    pub path: AbsPathBuf,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl FromStr for Position {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Position> {
+        let (path_line, column) = s
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow::format_err!("expected PATH:LINE:COLUMN, got `{}`", s))?;
+        let (path, line) = path_line
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow::format_err!("expected PATH:LINE:COLUMN, got `{}`", s))?;
+        let path = AbsPathBuf::assert(env::current_dir()?.join(path));
+        Ok(Position { path, line: line.parse()?, column: column.parse()? })
+    }
+}
+
+pub struct BenchCmd {
+    pub path: PathBuf,
+    pub what: BenchWhat,
+    pub memory_usage: bool,
+    pub load_output_dirs: bool,
+    pub with_proc_macro: bool,
+}
+
+pub enum BenchWhat {
+    Highlight { path: AbsPathBuf },
+    Complete(Position),
+    GotoDef(Position),
+}
+
+/// Reads `path` if given, otherwise all of stdin; this is the fallback used
+/// by every subcommand that used to only accept stdin.
+fn read_input(path: Option<&Path>) -> Result<String> {
+    match path {
+        Some(path) => Ok(fs::read_to_string(path)?),
+        None => {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)?;
+            Ok(buf)
+        }
+    }
+}
+
+pub fn parse(no_dump: bool, path: Option<PathBuf>) -> Result<()> {
+    let text = read_input(path.as_deref())?;
+    let file = syntax::SourceFile::parse(&text);
+    if !no_dump {
+        println!("{:#?}", file.tree().syntax());
+    }
+    Ok(())
+}
+
+pub fn symbols(path: Option<PathBuf>) -> Result<()> {
+    let text = read_input(path.as_deref())?;
+    let (analysis, file_id) = Analysis::from_single_file(text);
+    for symbol in analysis.file_structure(file_id)? {
+        println!("{:?}", symbol);
+    }
+    Ok(())
+}
+
+pub fn highlight(rainbow: bool, path: Option<PathBuf>) -> Result<()> {
+    let text = read_input(path.as_deref())?;
+    let (analysis, file_id) = Analysis::from_single_file(text);
+    let html = analysis.highlight_as_html(file_id, rainbow)?;
+    println!("{}", html);
+    Ok(())
+}
+
+impl BenchCmd {
+    pub fn run(self) -> Result<()> {
+        anyhow::bail!("analysis-bench is out of scope of this checkout")
+    }
+}
+
+pub fn run_proc_macro_srv() -> Result<()> {
+    anyhow::bail!("proc-macro-srv is out of scope of this checkout")
+}
+
+pub fn apply_ssr_rules(_rules: Vec<ssr::SsrRule>) -> Result<()> {
+    anyhow::bail!("ssr is out of scope of this checkout")
+}
+
+pub fn search_for_patterns(
+    _patterns: Vec<ssr::SsrPattern>,
+    _debug_snippet: Option<String>,
+) -> Result<()> {
+    anyhow::bail!("search is out of scope of this checkout")
+}
+
+/// Loads the Cargo project at `path` once; shared by every batch command
+/// that analyzes a whole workspace (`diagnostics`, `analysis-stats`, `lsif`).
+pub(crate) fn load_workspace(
+    path: &Path,
+    load_output_dirs: bool,
+    with_proc_macro: bool,
+) -> Result<(AnalysisHost, Vfs)> {
+    let cargo_config = CargoConfig::default();
+    let load_cargo_config = LoadCargoConfig {
+        load_out_dirs_from_check: load_output_dirs,
+        with_proc_macro,
+        prefill_caches: false,
+    };
+    let (host, vfs, _proc_macro_server) =
+        load_workspace_at(path, &cargo_config, &load_cargo_config, &|_| {})?;
+    Ok((host, vfs))
+}
@@ -0,0 +1,19 @@
+//! Library half of `rust-analyzer`, split out from the `bin` crate so the
+//! batch-mode CLI (`src/bin/main.rs`) and the LSP server can share code.
+
+pub mod cli;
+
+/// Dispatch target for the LSP server loop. In this checkout it starts no
+/// server and always returns an error.
+///
+/// Not touched by the batch-mode work in `cli` — kept as a thin placeholder
+/// here so `src/bin/main.rs` has a real dispatch target for every `Command`
+/// variant.
+pub fn run_server() -> anyhow::Result<()> {
+    anyhow::bail!("the LSP server loop is out of scope of this checkout")
+}
+
+/// The `--version` string.
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}